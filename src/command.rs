@@ -1,10 +1,11 @@
 use std::collections::HashMap;
 
+use color_eyre::eyre::eyre;
 use execute::shell;
 use serde::{Deserialize, Serialize};
 
 use crate::git::branch_name_from_issue;
-use crate::releases::get_version;
+use crate::releases::{changelog_entry_from_commits, get_version, previous_version};
 use crate::step::StepError;
 use crate::{state, RunType, State};
 
@@ -16,6 +17,13 @@ pub(crate) enum Variable {
     /// The generated branch name for the selected issue. Note that this means the workflow must
     /// already be in [`State::IssueSelected`] when this variable is used.
     IssueBranch,
+    /// The name of the first supported package found in your project: the crate name for Cargo,
+    /// or the manifest's file name (`pyproject.toml` / `package.json`) otherwise.
+    PackageName,
+    /// The release notes generated from the Conventional Commits made since the last release.
+    ChangelogEntry,
+    /// The version of the last release, read from git tags.
+    PreviousVersion,
 }
 
 /// Run the command string `command` in the current shell after replacing the keys of `variables`
@@ -51,13 +59,34 @@ fn replace_variables(
 ) -> Result<String, StepError> {
     for (var_name, var_type) in variables {
         match var_type {
-            Variable::Version => command = command.replace(&var_name, &get_version()?.to_string()),
+            Variable::Version => {
+                let package = get_version(None, false)?
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| eyre!("No supported metadata found to parse version from"))?;
+                command = command.replace(&var_name, &package.to_string());
+            }
             Variable::IssueBranch => match &state.issue {
                 state::Issue::Initial => return Err(StepError::NoIssueSelected),
                 state::Issue::Selected(issue) => {
                     command = command.replace(&var_name, &branch_name_from_issue(issue));
                 }
             },
+            Variable::PackageName => {
+                let package = get_version(None, false)?
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| eyre!("No supported metadata found to parse version from"))?;
+                command = command.replace(&var_name, &package.name);
+            }
+            Variable::ChangelogEntry => {
+                let changelog = changelog_entry_from_commits()?;
+                command = command.replace(&var_name, &changelog);
+            }
+            Variable::PreviousVersion => {
+                let version = previous_version()?;
+                command = command.replace(&var_name, &version.to_string());
+            }
         }
     }
     Ok(command)
@@ -92,6 +121,15 @@ mod test_replace_variables {
 
     use super::*;
 
+    fn first_version() -> String {
+        get_version(None, false)
+            .unwrap()
+            .into_iter()
+            .next()
+            .unwrap()
+            .to_string()
+    }
+
     #[test]
     fn multiple_variables() {
         let command = "blah $$ branch_name".to_string();
@@ -115,7 +153,7 @@ mod test_replace_variables {
 
         assert_eq!(
             command,
-            format!("blah {} {}", get_version().unwrap(), expected_branch_name)
+            format!("blah {} {}", first_version(), expected_branch_name)
         );
     }
 
@@ -128,10 +166,7 @@ mod test_replace_variables {
 
         let command = replace_variables(command, variables, &state).unwrap();
 
-        assert_eq!(
-            command,
-            format!("blah {} other blah", get_version().unwrap(),)
-        );
+        assert_eq!(command, format!("blah {} other blah", first_version()));
     }
 
     #[test]
@@ -156,4 +191,48 @@ mod test_replace_variables {
 
         assert_eq!(command, format!("blah {} other blah", expected_branch_name));
     }
+
+    #[test]
+    fn replace_package_name() {
+        let command = "blah $$ other blah".to_string();
+        let mut variables = HashMap::new();
+        variables.insert("$$".to_string(), Variable::PackageName);
+        let state = State::new(None, None);
+
+        let command = replace_variables(command, variables, &state).unwrap();
+        let expected_name = get_version(None, false)
+            .unwrap()
+            .into_iter()
+            .next()
+            .unwrap()
+            .name;
+
+        assert_eq!(command, format!("blah {} other blah", expected_name));
+    }
+
+    #[test]
+    fn replace_changelog_entry() {
+        let command = "blah $$ other blah".to_string();
+        let mut variables = HashMap::new();
+        variables.insert("$$".to_string(), Variable::ChangelogEntry);
+        let state = State::new(None, None);
+
+        let command = replace_variables(command, variables, &state).unwrap();
+        let expected_changelog = changelog_entry_from_commits().unwrap();
+
+        assert_eq!(command, format!("blah {} other blah", expected_changelog));
+    }
+
+    #[test]
+    fn replace_previous_version() {
+        let command = "blah $$ other blah".to_string();
+        let mut variables = HashMap::new();
+        variables.insert("$$".to_string(), Variable::PreviousVersion);
+        let state = State::new(None, None);
+
+        let command = replace_variables(command, variables, &state).unwrap();
+        let expected_version = previous_version().unwrap();
+
+        assert_eq!(command, format!("blah {} other blah", expected_version));
+    }
 }
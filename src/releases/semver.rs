@@ -1,15 +1,17 @@
 use std::fmt::Display;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
 use color_eyre::eyre::WrapErr;
 use color_eyre::eyre::{eyre, Result};
 use semver::{Prerelease, Version};
 use serde::Deserialize;
 
-use crate::{package_json, pyproject, state};
+use crate::{package_json, pyproject, state, RunType};
 
 /// The various rules that can be used when bumping the current version of a project via
 /// [`crate::step::Step::BumpVersion`].
-#[derive(Debug, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Deserialize, PartialEq, Eq, Clone)]
 #[serde(tag = "rule")]
 pub(crate) enum Rule {
     Major,
@@ -21,6 +23,14 @@ pub(crate) enum Rule {
         fallback_rule: ConventionalRule,
     },
     Release,
+    /// Detect the rule to apply from the Conventional Commits made since the last release.
+    Auto,
+    /// Set the version to an explicit target instead of bumping relative to the current one.
+    /// `version` may be a full SemVer version (`1.2.3`) or a partial one (`1.2`), in which case
+    /// the missing components are filled in from the current version.
+    Set {
+        version: String,
+    },
 }
 
 impl From<ConventionalRule> for Rule {
@@ -34,7 +44,7 @@ impl From<ConventionalRule> for Rule {
 }
 
 /// The rules that can be derived from Conventional Commits.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub(crate) enum ConventionalRule {
     Major,
     Minor,
@@ -47,10 +57,187 @@ impl Default for ConventionalRule {
     }
 }
 
+impl ConventionalRule {
+    /// The relative precedence of this rule, used to pick the most significant rule out of
+    /// several commits (higher wins).
+    fn precedence(&self) -> u8 {
+        match self {
+            ConventionalRule::Patch => 0,
+            ConventionalRule::Minor => 1,
+            ConventionalRule::Major => 2,
+        }
+    }
+}
+
+/// Walk the commits from `HEAD` back to the most recent version tag and determine the
+/// [`ConventionalRule`] implied by the highest-precedence [Conventional Commit](https://www.conventionalcommits.org)
+/// found, defaulting to [`ConventionalRule::Patch`] when none qualify.
+pub(crate) fn conventional_rule_from_commits() -> Result<ConventionalRule> {
+    conventional_rule_from_commits_in(".")
+}
+
+fn conventional_rule_from_commits_in(path: &str) -> Result<ConventionalRule> {
+    let repo = git2::Repository::open(path).wrap_err("Could not open the git repository")?;
+    let mut rule = None;
+    for message in commit_messages_since_last_tag(&repo)? {
+        if let Some(commit_rule) = conventional_rule_for_commit(&message) {
+            rule = Some(match rule {
+                Some(existing) if existing.precedence() >= commit_rule.precedence() => existing,
+                _ => commit_rule,
+            });
+        }
+    }
+    Ok(rule.unwrap_or_default())
+}
+
+/// The version of the most recently tagged release, read from git tags in the current directory.
+pub(crate) fn previous_version() -> Result<Version> {
+    previous_version_in(".")
+}
+
+fn previous_version_in(path: &str) -> Result<Version> {
+    let repo = git2::Repository::open(path).wrap_err("Could not open the git repository")?;
+    most_recent_version_tag(&repo)?
+        .map(|(version, _)| version)
+        .ok_or_else(|| eyre!("Could not find a previous version tag"))
+}
+
+/// Generate release notes for the pending release from the Conventional Commits made since the
+/// last version tag, grouped into Breaking Changes / Features / Fixes sections.
+pub(crate) fn changelog_entry_from_commits() -> Result<String> {
+    changelog_entry_from_commits_in(".")
+}
+
+fn changelog_entry_from_commits_in(path: &str) -> Result<String> {
+    let repo = git2::Repository::open(path).wrap_err("Could not open the git repository")?;
+
+    let mut breaking = Vec::new();
+    let mut features = Vec::new();
+    let mut fixes = Vec::new();
+    for message in commit_messages_since_last_tag(&repo)? {
+        let Some(subject) = message.lines().next() else {
+            continue;
+        };
+        match conventional_rule_for_commit(&message) {
+            Some(ConventionalRule::Major) => breaking.push(subject.to_string()),
+            Some(ConventionalRule::Minor) => features.push(subject.to_string()),
+            // `ConventionalRule::Patch` also covers non-user-facing types (docs, chore, ci, ...);
+            // only `fix` commits are actually worth surfacing in release notes.
+            Some(ConventionalRule::Patch) if commit_type_for_commit(&message) == Some("fix") => {
+                fixes.push(subject.to_string());
+            }
+            Some(ConventionalRule::Patch) | None => {}
+        }
+    }
+
+    if breaking.is_empty() && features.is_empty() && fixes.is_empty() {
+        return Err(eyre!(
+            "No Conventional Commits found to generate a changelog entry from"
+        ));
+    }
+
+    let mut entry = String::new();
+    for (title, items) in [
+        ("Breaking Changes", &breaking),
+        ("Features", &features),
+        ("Fixes", &fixes),
+    ] {
+        if items.is_empty() {
+            continue;
+        }
+        entry.push_str(&format!("### {}\n\n", title));
+        for item in items {
+            entry.push_str(&format!("- {}\n", item));
+        }
+        entry.push('\n');
+    }
+    Ok(entry.trim_end().to_string())
+}
+
+/// Collect the full message (subject + body) of every commit from `HEAD` back to (but
+/// excluding) the most recent version tag.
+fn commit_messages_since_last_tag(repo: &git2::Repository) -> Result<Vec<String>> {
+    let mut revwalk = repo.revwalk().wrap_err("Could not walk the git history")?;
+    revwalk.push_head().wrap_err("Could not find HEAD")?;
+    if let Some((_, tag_oid)) = most_recent_version_tag(repo)? {
+        revwalk
+            .hide(tag_oid)
+            .wrap_err("Could not stop walking history at the last version tag")?;
+    }
+    revwalk
+        .map(|oid| {
+            let commit = repo.find_commit(oid?)?;
+            Ok(commit.message().unwrap_or_default().to_string())
+        })
+        .collect()
+}
+
+/// Find the version and commit of the most recent (by SemVer ordering) version tag, if any.
+fn most_recent_version_tag(repo: &git2::Repository) -> Result<Option<(Version, git2::Oid)>> {
+    let tag_names = repo.tag_names(None).wrap_err("Could not list git tags")?;
+    let mut tagged_versions = Vec::new();
+    for name in tag_names.iter().flatten() {
+        let version_str = name.strip_prefix('v').unwrap_or(name);
+        let Ok(version) = Version::parse(version_str) else {
+            continue;
+        };
+        let Ok(reference) = repo.find_reference(&format!("refs/tags/{}", name)) else {
+            continue;
+        };
+        if let Ok(commit) = reference.peel_to_commit() {
+            tagged_versions.push((version, commit.id()));
+        }
+    }
+    tagged_versions.sort_by(|(a, _), (b, _)| a.cmp(b));
+    Ok(tagged_versions.pop())
+}
+
+/// Classify a single commit message per the Conventional Commits grammar, returning `None` if
+/// the commit doesn't follow the convention (and therefore shouldn't influence the bump).
+fn conventional_rule_for_commit(message: &str) -> Option<ConventionalRule> {
+    if message.contains("BREAKING CHANGE:") || message.contains("BREAKING-CHANGE:") {
+        return Some(ConventionalRule::Major);
+    }
+    let subject = message.lines().next()?;
+    let colon_index = subject.find(':')?;
+    let commit_type = &subject[..colon_index];
+    if commit_type.ends_with('!') {
+        return Some(ConventionalRule::Major);
+    }
+    match commit_type_for_commit(message)? {
+        "feat" => Some(ConventionalRule::Minor),
+        "fix" | "perf" | "refactor" | "revert" | "build" | "chore" | "ci" | "docs" | "style"
+        | "test" => Some(ConventionalRule::Patch),
+        _ => None,
+    }
+}
+
+/// Extract the Conventional Commits type (`feat`, `fix`, ...) from a commit message's subject
+/// line, with any `(scope)` and breaking-change `!` stripped. Returns `None` if the subject has
+/// no `:`; whatever precedes the first `:` is returned without checking it is a known type.
+fn commit_type_for_commit(message: &str) -> Option<&str> {
+    let subject = message.lines().next()?;
+    let colon_index = subject.find(':')?;
+    let commit_type = &subject[..colon_index];
+    Some(
+        commit_type
+            .split('(')
+            .next()
+            .unwrap_or(commit_type)
+            .trim_end_matches('!'),
+    )
+}
+
 #[derive(Debug, Eq, PartialEq)]
 pub(crate) struct PackageVersion {
     pub(crate) version: Version,
+    /// The name of the package, used to scope a bump to a subset of packages in a workspace and
+    /// to resolve the `PackageName` command variable. For Cargo packages this is the crate name;
+    /// for pyproject.toml and package.json it is the manifest's file name, not a name read from it.
+    pub(crate) name: String,
     package_manager: PackageManager,
+    /// Path to the manifest file (e.g. `Cargo.toml`) that this version was found in.
+    path: PathBuf,
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -60,80 +247,252 @@ pub(crate) enum PackageManager {
     JavaScript,
 }
 
+impl Display for PackageManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            PackageManager::Cargo => "Cargo",
+            PackageManager::Poetry => "Poetry",
+            PackageManager::JavaScript => "JavaScript",
+        };
+        write!(f, "{name}")
+    }
+}
+
 impl Display for PackageVersion {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.version)
     }
 }
 
-pub(super) fn bump_version(rule: Rule, dry_run: bool) -> Result<semver::Version> {
-    let mut package_version = get_version()?;
-    package_version.version =
-        bump(package_version.version, rule).wrap_err("While bumping version")?;
-    if !dry_run {
-        set_version(&package_version)?;
+/// Bump every package in `packages` (or every discovered package, if `None`) by `rule`.
+///
+/// When `sync` is `true`, every supported manifest present in the repo (not just whichever is
+/// found first) is bumped, after verifying that the non-Cargo manifests agree with the Cargo
+/// version (see [`get_version`]); Cargo workspace members are not compared with each other.
+///
+/// On a dry run, nothing is written to disk; instead, a "Would bump..." line is written to
+/// `dry_run_stdout` for every package describing what would have changed.
+pub(super) fn bump_version(
+    rule: Rule,
+    packages: Option<&[String]>,
+    sync: bool,
+    allow_downgrade: bool,
+    dry_run_stdout: Option<&mut dyn Write>,
+) -> Result<Vec<Version>> {
+    let rule = if let Rule::Auto = rule {
+        conventional_rule_from_commits()?.into()
+    } else {
+        rule
+    };
+    bump_packages(
+        get_version(packages, sync)?,
+        rule,
+        allow_downgrade,
+        dry_run_stdout,
+    )
+}
+
+/// Bump an already-discovered set of packages by `rule`, either writing the new versions to disk
+/// or, on a dry run, describing the change for each package on `dry_run_stdout`.
+///
+/// `rule` must already be resolved (never [`Rule::Auto`]) so the dry-run preview names the
+/// concrete rule that was applied rather than the literal `Auto`.
+fn bump_packages(
+    packages: Vec<PackageVersion>,
+    rule: Rule,
+    allow_downgrade: bool,
+    mut dry_run_stdout: Option<&mut dyn Write>,
+) -> Result<Vec<Version>> {
+    let mut new_versions = Vec::new();
+    for mut package_version in packages {
+        let old_version = package_version.version.clone();
+        package_version.version = bump(package_version.version, rule.clone(), allow_downgrade)
+            .wrap_err_with(|| format!("While bumping {}", package_version.name))?;
+        if let Some(stdout) = dry_run_stdout.as_deref_mut() {
+            writeln!(
+                stdout,
+                "Would bump {} ({}): {} -> {} [{}]",
+                package_version.path.display(),
+                package_version.package_manager,
+                old_version,
+                package_version.version,
+                describe_rule(&rule)
+            )?;
+        } else {
+            set_version(&package_version)?;
+        }
+        new_versions.push(package_version.version.clone());
+    }
+    Ok(new_versions)
+}
+
+/// A short human-readable description of a [`Rule`], used in dry-run output.
+fn describe_rule(rule: &Rule) -> String {
+    match rule {
+        Rule::Major => "Major".to_string(),
+        Rule::Minor => "Minor".to_string(),
+        Rule::Patch => "Patch".to_string(),
+        Rule::Release => "Release".to_string(),
+        Rule::Auto => "Auto".to_string(),
+        Rule::Pre { label, .. } => format!("Pre({label})"),
+        Rule::Set { version } => format!("Set({version})"),
     }
-    Ok(package_version.version)
 }
 
 pub(crate) fn bump_version_and_update_state(
-    mut state: state::State,
+    mut run_type: RunType,
     rule: Rule,
-) -> Result<state::State> {
-    let version = bump_version(rule, false)?;
+    packages: Option<&[String]>,
+    sync: bool,
+    allow_downgrade: bool,
+) -> Result<RunType> {
+    let (state, dry_run_stdout) = match &mut run_type {
+        RunType::DryRun { state, stdout } => (state, Some(stdout as &mut dyn Write)),
+        RunType::Real(state) => (state, None),
+    };
+    let version = bump_version(rule, packages, sync, allow_downgrade, dry_run_stdout)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| eyre!("No packages found to bump"))?;
     state.release = state::Release::Bumped(version);
-    Ok(state)
+    Ok(run_type)
 }
 
-pub(crate) fn get_version() -> Result<PackageVersion> {
-    if let Some(cargo_version) = crate::cargo::get_version("Cargo.toml") {
-        let version = semver::Version::parse(&cargo_version).wrap_err_with(|| {
-            format!(
-                "Found {} in Cargo.toml which is not a valid version",
-                cargo_version
-            )
-        })?;
-        Ok(PackageVersion {
-            version,
-            package_manager: PackageManager::Cargo,
+/// Discover every package whose version knope can read and write, optionally scoped down to
+/// `packages` by name.
+///
+/// Normally, the first supported manifest found (Cargo workspace, then pyproject.toml, then
+/// package.json) wins and the rest are ignored. When `sync` is `true`, every manifest present is
+/// collected instead. The Cargo-reported version (the first workspace member found, if any) must
+/// agree with every non-Cargo manifest (e.g. a Rust crate shipped with an npm wrapper), or this
+/// errors out with the mismatch; other workspace members are free to carry their own independent
+/// versions and are not compared against each other.
+pub(crate) fn get_version(packages: Option<&[String]>, sync: bool) -> Result<Vec<PackageVersion>> {
+    let mut found = if sync {
+        let cargo_found = cargo_package_versions()?;
+        let other_found = other_package_versions()?;
+        verify_versions_agree(&cargo_found, &other_found)?;
+        let mut found = cargo_found;
+        found.extend(other_found);
+        found
+    } else {
+        let cargo_found = cargo_package_versions()?;
+        if cargo_found.is_empty() {
+            // The first supported non-Cargo manifest wins; the rest are ignored.
+            other_package_versions()?.into_iter().take(1).collect()
+        } else {
+            cargo_found
+        }
+    };
+    if let Some(packages) = packages {
+        found.retain(|package| packages.iter().any(|name| name == &package.name));
+    }
+    if found.is_empty() {
+        return Err(eyre!("No supported metadata found to parse version from"));
+    }
+    Ok(found)
+}
+
+/// Verify that every manifest agrees on a single version, erroring with a diff of the mismatched
+/// manifests otherwise. Workspace members in `cargo_packages` are independently versioned and are
+/// intentionally not compared against each other; instead, the first Cargo package (if any) is
+/// used as the baseline that every manifest in `other_packages` must agree with. If there's no
+/// Cargo package at all, the first of `other_packages` becomes the baseline and the rest are
+/// compared against it instead.
+fn verify_versions_agree(
+    cargo_packages: &[PackageVersion],
+    other_packages: &[PackageVersion],
+) -> Result<()> {
+    let mut other_packages = other_packages.iter();
+    let baseline = match cargo_packages.first() {
+        Some(cargo_version) => cargo_version,
+        None => match other_packages.next() {
+            Some(first_other) => first_other,
+            None => return Ok(()),
+        },
+    };
+    for package in other_packages {
+        if package.version != baseline.version {
+            return Err(eyre!(
+                "Metadata files have mismatched versions: {} is {} but {} is {}",
+                baseline.path.display(),
+                baseline.version,
+                package.path.display(),
+                package.version
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Walk a Cargo workspace (or single crate) via `cargo metadata`, returning one
+/// [`PackageVersion`] per member. Returns an empty `Vec` if there's no `Cargo.toml` in the
+/// current directory.
+fn cargo_package_versions() -> Result<Vec<PackageVersion>> {
+    if !Path::new("Cargo.toml").exists() {
+        return Ok(Vec::new());
+    }
+    let metadata = cargo_metadata::MetadataCommand::new()
+        .exec()
+        .wrap_err("Could not read Cargo workspace metadata")?;
+    metadata
+        .workspace_packages()
+        .into_iter()
+        .map(|package| {
+            Ok(PackageVersion {
+                version: package.version.clone(),
+                name: package.name.clone(),
+                package_manager: PackageManager::Cargo,
+                path: package.manifest_path.clone().into_std_path_buf(),
+            })
         })
-    } else if let Some(pyproject_version) = pyproject::get_version("pyproject.toml") {
-        let version = semver::Version::parse(&pyproject_version).wrap_err_with(|| {
+        .collect()
+}
+
+/// Read every non-Cargo manifest knope supports (pyproject.toml, then package.json) that is
+/// present. Used when there's no `Cargo.toml`, and in sync mode alongside the Cargo packages.
+fn other_package_versions() -> Result<Vec<PackageVersion>> {
+    let mut found = Vec::new();
+    if let Some(pyproject_version) = pyproject::get_version("pyproject.toml") {
+        let version = Version::parse(&pyproject_version).wrap_err_with(|| {
             format!(
                 "Found {} in pyproject.toml which is not a valid version",
                 pyproject_version
             )
         })?;
-        Ok(PackageVersion {
+        found.push(PackageVersion {
             version,
+            name: "pyproject.toml".to_string(),
             package_manager: PackageManager::Poetry,
-        })
-    } else if let Some(package_version) = package_json::get_version("package.json") {
-        let version = semver::Version::parse(&package_version).wrap_err_with(|| {
+            path: PathBuf::from("pyproject.toml"),
+        });
+    }
+    if let Some(package_version) = package_json::get_version("package.json") {
+        let version = Version::parse(&package_version).wrap_err_with(|| {
             format!(
                 "Found {} in package.json which is not a valid version",
                 package_version
             )
         })?;
-        Ok(PackageVersion {
+        found.push(PackageVersion {
             version,
+            name: "package.json".to_string(),
             package_manager: PackageManager::JavaScript,
-        })
-    } else {
-        Err(eyre!("No supported metadata found to parse version from"))
+            path: PathBuf::from("package.json"),
+        });
     }
+    Ok(found)
 }
 
-fn set_version(version: &PackageVersion) -> Result<()> {
-    match version.package_manager {
-        PackageManager::Cargo => crate::cargo::set_version("Cargo.toml", &version.to_string())
-            .wrap_err("While bumping Cargo.toml"),
-        PackageManager::Poetry => pyproject::set_version("pyproject.toml", &version.to_string())
-            .wrap_err("While bumping pyproject.toml"),
-        PackageManager::JavaScript => {
-            package_json::set_version("package.json", &version.to_string())
-                .wrap_err("While bumping package.json")
-        }
+fn set_version(package: &PackageVersion) -> Result<()> {
+    let path = package.path.to_string_lossy();
+    match package.package_manager {
+        PackageManager::Cargo => crate::cargo::set_version(&path, &package.to_string())
+            .wrap_err_with(|| format!("While bumping {}", path)),
+        PackageManager::Poetry => pyproject::set_version(&path, &package.to_string())
+            .wrap_err_with(|| format!("While bumping {}", path)),
+        PackageManager::JavaScript => package_json::set_version(&path, &package.to_string())
+            .wrap_err_with(|| format!("While bumping {}", path)),
     }
 }
 
@@ -145,7 +504,7 @@ fn set_version(version: &PackageVersion) -> Result<()> {
 /// different behavior:
 /// 1. [`Rule::Major`] will bump the minor component.
 /// 2. [`Rule::Minor`] will bump the patch component.
-fn bump(mut version: Version, rule: Rule) -> Result<Version> {
+fn bump(mut version: Version, rule: Rule, allow_downgrade: bool) -> Result<Version> {
     let is_0 = version.major == 0;
     match (rule, is_0) {
         (Rule::Major, false) => {
@@ -177,6 +536,182 @@ fn bump(mut version: Version, rule: Rule) -> Result<Version> {
             },
             _,
         ) => bump_pre(version, &prefix, fallback_rule),
+        (Rule::Auto, _) => bump(
+            version,
+            conventional_rule_from_commits()?.into(),
+            allow_downgrade,
+        ),
+        (Rule::Set { version: target }, _) => {
+            let target = parse_target_version(&target, &version)?;
+            if target < version && !allow_downgrade {
+                return Err(eyre!(
+                    "{target} is lower than the current version {version}; pass --allow-downgrade to allow a downgrade"
+                ));
+            }
+            Ok(target)
+        }
+    }
+}
+
+/// Parse a `Rule::Set` target into a concrete [`Version`], filling in any component missing from
+/// a partial version (`1.2`) with the matching component of `current`, mirroring cargo's
+/// `PartialVersion` handling.
+fn parse_target_version(input: &str, current: &Version) -> Result<Version> {
+    if let Ok(version) = Version::parse(input) {
+        return Ok(version);
+    }
+    let mut parts = input.split('.');
+    let major = parts
+        .next()
+        .filter(|part| !part.is_empty())
+        .ok_or_else(|| eyre!("{input} is not a valid version"))?
+        .parse::<u64>()
+        .wrap_err_with(|| format!("{input} is not a valid version"))?;
+    let minor = parts
+        .next()
+        .map(str::parse::<u64>)
+        .transpose()
+        .wrap_err_with(|| format!("{input} is not a valid version"))?
+        .unwrap_or(current.minor);
+    let patch = parts
+        .next()
+        .map(str::parse::<u64>)
+        .transpose()
+        .wrap_err_with(|| format!("{input} is not a valid version"))?
+        .unwrap_or(current.patch);
+    if parts.next().is_some() {
+        return Err(eyre!("{input} is not a valid version"));
+    }
+    Ok(Version::new(major, minor, patch))
+}
+
+#[cfg(test)]
+mod test_verify_versions_agree {
+    use super::*;
+
+    fn package(version: &str, path: &str) -> PackageVersion {
+        PackageVersion {
+            version: Version::parse(version).unwrap(),
+            name: path.to_string(),
+            package_manager: PackageManager::Cargo,
+            path: PathBuf::from(path),
+        }
+    }
+
+    #[test]
+    fn agrees_when_all_match() {
+        let cargo_packages = vec![package("1.2.3", "Cargo.toml")];
+        let other_packages = vec![package("1.2.3", "package.json")];
+        assert!(verify_versions_agree(&cargo_packages, &other_packages).is_ok());
+    }
+
+    #[test]
+    fn errors_on_mismatch() {
+        let cargo_packages = vec![package("1.2.3", "Cargo.toml")];
+        let other_packages = vec![package("1.2.4", "package.json")];
+        assert!(verify_versions_agree(&cargo_packages, &other_packages).is_err());
+    }
+
+    #[test]
+    fn workspace_members_are_not_compared_against_each_other() {
+        let cargo_packages = vec![
+            package("1.2.3", "crates/a/Cargo.toml"),
+            package("4.5.6", "crates/b/Cargo.toml"),
+        ];
+        let other_packages = vec![package("1.2.3", "package.json")];
+        assert!(verify_versions_agree(&cargo_packages, &other_packages).is_ok());
+    }
+
+    #[test]
+    fn agrees_when_no_cargo_package_and_others_match() {
+        let cargo_packages = vec![];
+        let other_packages = vec![
+            package("1.0.0", "pyproject.toml"),
+            package("1.0.0", "package.json"),
+        ];
+        assert!(verify_versions_agree(&cargo_packages, &other_packages).is_ok());
+    }
+
+    #[test]
+    fn errors_on_mismatch_with_no_cargo_package() {
+        let cargo_packages = vec![];
+        let other_packages = vec![
+            package("1.0.0", "pyproject.toml"),
+            package("2.0.0", "package.json"),
+        ];
+        assert!(verify_versions_agree(&cargo_packages, &other_packages).is_err());
+    }
+}
+
+#[cfg(test)]
+mod test_describe_rule {
+    use super::*;
+
+    #[test]
+    fn describes_simple_rules() {
+        assert_eq!(describe_rule(&Rule::Major), "Major");
+        assert_eq!(describe_rule(&Rule::Auto), "Auto");
+    }
+
+    #[test]
+    fn describes_pre_with_label() {
+        let rule = Rule::Pre {
+            label: "rc".to_string(),
+            fallback_rule: ConventionalRule::Minor,
+        };
+        assert_eq!(describe_rule(&rule), "Pre(rc)");
+    }
+
+    #[test]
+    fn describes_set_with_target() {
+        let rule = Rule::Set {
+            version: "1.2.3".to_string(),
+        };
+        assert_eq!(describe_rule(&rule), "Set(1.2.3)");
+    }
+}
+
+#[cfg(test)]
+mod test_bump_packages {
+    use super::*;
+
+    fn package(version: &str, path: &str) -> PackageVersion {
+        PackageVersion {
+            version: Version::parse(version).unwrap(),
+            name: path.to_string(),
+            package_manager: PackageManager::Cargo,
+            path: PathBuf::from(path),
+        }
+    }
+
+    #[test]
+    fn dry_run_describes_the_resolved_rule_not_auto() {
+        let packages = vec![package("1.2.3", "Cargo.toml")];
+        let mut stdout = Vec::new();
+
+        bump_packages(packages, Rule::Minor, false, Some(&mut stdout)).unwrap();
+
+        let output = String::from_utf8(stdout).unwrap();
+        assert_eq!(
+            output,
+            "Would bump Cargo.toml (Cargo): 1.2.3 -> 1.3.0 [Minor]\n"
+        );
+    }
+
+    #[test]
+    fn dry_run_writes_one_line_per_package() {
+        let packages = vec![
+            package("1.2.3", "Cargo.toml"),
+            package("4.5.6", "package.json"),
+        ];
+        let mut stdout = Vec::new();
+
+        bump_packages(packages, Rule::Patch, false, Some(&mut stdout)).unwrap();
+
+        let output = String::from_utf8(stdout).unwrap();
+        assert_eq!(output.lines().count(), 2);
+        assert!(output.contains("Would bump Cargo.toml (Cargo): 1.2.3 -> 1.2.4 [Patch]"));
+        assert!(output.contains("Would bump package.json"));
     }
 }
 
@@ -187,7 +722,7 @@ mod test_bump {
     #[test]
     fn major() {
         let version = Version::new(1, 2, 3);
-        let version = bump(version, Rule::Major).unwrap();
+        let version = bump(version, Rule::Major, false).unwrap();
 
         assert_eq!(version, Version::new(2, 0, 0));
     }
@@ -195,7 +730,7 @@ mod test_bump {
     #[test]
     fn major_0() {
         let version = Version::new(0, 1, 2);
-        let version = bump(version, Rule::Major).unwrap();
+        let version = bump(version, Rule::Major, false).unwrap();
 
         assert_eq!(version, Version::new(0, 2, 0));
     }
@@ -203,7 +738,7 @@ mod test_bump {
     #[test]
     fn minor() {
         let version = Version::new(1, 2, 3);
-        let version = bump(version, Rule::Minor).unwrap();
+        let version = bump(version, Rule::Minor, false).unwrap();
 
         assert_eq!(version, Version::new(1, 3, 0));
     }
@@ -211,7 +746,7 @@ mod test_bump {
     #[test]
     fn minor_0() {
         let version = Version::new(0, 1, 2);
-        let version = bump(version, Rule::Minor).unwrap();
+        let version = bump(version, Rule::Minor, false).unwrap();
 
         assert_eq!(version, Version::new(0, 1, 3));
     }
@@ -219,7 +754,7 @@ mod test_bump {
     #[test]
     fn patch() {
         let version = Version::new(1, 2, 3);
-        let version = bump(version, Rule::Patch).unwrap();
+        let version = bump(version, Rule::Patch, false).unwrap();
 
         assert_eq!(version, Version::new(1, 2, 4));
     }
@@ -227,7 +762,7 @@ mod test_bump {
     #[test]
     fn patch_0() {
         let version = Version::new(1, 2, 3);
-        let version = bump(version, Rule::Patch).unwrap();
+        let version = bump(version, Rule::Patch, false).unwrap();
 
         assert_eq!(version, Version::new(1, 2, 4));
     }
@@ -241,6 +776,7 @@ mod test_bump {
                 label: String::from("rc"),
                 fallback_rule: ConventionalRule::Minor,
             },
+            false,
         )
         .unwrap();
 
@@ -250,10 +786,69 @@ mod test_bump {
     #[test]
     fn release() {
         let version = Version::parse("1.2.3-rc.0").unwrap();
-        let version = bump(version, Rule::Release).unwrap();
+        let version = bump(version, Rule::Release, false).unwrap();
 
         assert_eq!(version, Version::new(1, 2, 3));
     }
+
+    #[test]
+    fn set_full_version() {
+        let version = Version::new(1, 2, 3);
+        let version = bump(
+            version,
+            Rule::Set {
+                version: "2.0.0".to_string(),
+            },
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(version, Version::new(2, 0, 0));
+    }
+
+    #[test]
+    fn set_partial_version_fills_from_current() {
+        let version = Version::new(1, 2, 3);
+        let version = bump(
+            version,
+            Rule::Set {
+                version: "2".to_string(),
+            },
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(version, Version::new(2, 2, 3));
+    }
+
+    #[test]
+    fn set_rejects_downgrade_by_default() {
+        let version = Version::new(1, 2, 3);
+        let result = bump(
+            version,
+            Rule::Set {
+                version: "1.0.0".to_string(),
+            },
+            false,
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn set_allows_downgrade_when_permitted() {
+        let version = Version::new(1, 2, 3);
+        let version = bump(
+            version,
+            Rule::Set {
+                version: "1.0.0".to_string(),
+            },
+            true,
+        )
+        .unwrap();
+
+        assert_eq!(version, Version::new(1, 0, 0));
+    }
 }
 
 /// Bumps the pre-release component of a [`Version`].
@@ -262,34 +857,321 @@ mod test_bump {
 /// `semantic_rule` will be used to bump to primary components before the
 /// pre-release component is added.
 ///
-/// # Errors
-///
-/// Can fail if there is an existing pre-release component that can't be incremented.
+/// The prerelease is treated as a dot-separated label (possibly itself containing dots, e.g.
+/// `alpha.beta`) followed by an optional trailing numeric counter: `rc.1`, `alpha.beta.1`, and
+/// the bare `0` are all valid. If `prefix` matches the existing label, the counter is
+/// incremented (starting a new one at `0` if there wasn't one); otherwise the prerelease resets
+/// to `{prefix}.0`. Build metadata (`+build`), which lives in a separate field of [`Version`], is
+/// untouched either way.
 fn bump_pre(
     mut version: Version,
     prefix: &str,
     fallback_rule: ConventionalRule,
 ) -> Result<Version> {
     if version.pre.is_empty() {
-        let mut version = bump(version, fallback_rule.into())?;
+        let mut version = bump(version, fallback_rule.into(), false)?;
         version.pre = Prerelease::new(&format!("{}.0", prefix))?;
         return Ok(version);
     }
 
     let pre_string = version.pre.as_str();
     let parts = pre_string.split('.').collect::<Vec<_>>();
+    let counter = parts.last().and_then(|part| part.parse::<u64>().ok());
+    let label = match counter {
+        Some(_) => parts[..parts.len() - 1].join("."),
+        None => pre_string.to_string(),
+    };
 
-    if parts.len() != 2 {
-        return Err(eyre!(
-            "A prerelease version already exists but could not be incremented"
-        ));
+    let new_pre = if label == prefix {
+        format!("{}.{}", prefix, counter.map(|c| c + 1).unwrap_or(0))
+    } else {
+        format!("{}.0", prefix)
+    };
+    version.pre = Prerelease::new(&new_pre)?;
+    Ok(version)
+}
+
+#[cfg(test)]
+mod test_bump_pre {
+    use super::*;
+
+    #[test]
+    fn increments_existing_counter() {
+        let version = Version::parse("1.2.3-rc.1").unwrap();
+        let version = bump_pre(version, "rc", ConventionalRule::Minor).unwrap();
+
+        assert_eq!(version, Version::parse("1.2.3-rc.2").unwrap());
     }
 
-    if parts[0] != prefix {
-        version.pre = Prerelease::new(&format!("{}.0", prefix))?;
-        return Ok(version);
+    #[test]
+    fn starts_a_counter_when_label_has_none() {
+        let version = Version::parse("1.2.3-rc").unwrap();
+        let version = bump_pre(version, "rc", ConventionalRule::Minor).unwrap();
+
+        assert_eq!(version, Version::parse("1.2.3-rc.0").unwrap());
     }
-    let pre_version = parts[1].parse::<u16>()?;
-    version.pre = Prerelease::new(&format!("{}.{}", prefix, pre_version + 1))?;
-    Ok(version)
-}
\ No newline at end of file
+
+    #[test]
+    fn resets_when_label_differs() {
+        let version = Version::parse("1.2.3-alpha.3").unwrap();
+        let version = bump_pre(version, "beta", ConventionalRule::Minor).unwrap();
+
+        assert_eq!(version, Version::parse("1.2.3-beta.0").unwrap());
+    }
+
+    #[test]
+    fn increments_multi_segment_label() {
+        let version = Version::parse("1.2.3-alpha.beta.1").unwrap();
+        let version = bump_pre(version, "alpha.beta", ConventionalRule::Minor).unwrap();
+
+        assert_eq!(version, Version::parse("1.2.3-alpha.beta.2").unwrap());
+    }
+
+    #[test]
+    fn plain_numeric_prerelease_does_not_error() {
+        let version = Version::parse("1.2.3-0").unwrap();
+        let version = bump_pre(version, "rc", ConventionalRule::Minor).unwrap();
+
+        assert_eq!(version, Version::parse("1.2.3-rc.0").unwrap());
+    }
+
+    #[test]
+    fn preserves_build_metadata() {
+        let version = Version::parse("1.2.3-rc.1+build5").unwrap();
+        let version = bump_pre(version, "rc", ConventionalRule::Minor).unwrap();
+
+        assert_eq!(version, Version::parse("1.2.3-rc.2+build5").unwrap());
+    }
+}
+
+#[cfg(test)]
+mod test_conventional_rule_for_commit {
+    use super::*;
+
+    #[test]
+    fn feat_is_minor() {
+        assert_eq!(
+            conventional_rule_for_commit("feat: add new step"),
+            Some(ConventionalRule::Minor)
+        );
+    }
+
+    #[test]
+    fn feat_with_scope_is_minor() {
+        assert_eq!(
+            conventional_rule_for_commit("feat(releases): add new step"),
+            Some(ConventionalRule::Minor)
+        );
+    }
+
+    #[test]
+    fn fix_is_patch() {
+        assert_eq!(
+            conventional_rule_for_commit("fix: don't panic"),
+            Some(ConventionalRule::Patch)
+        );
+    }
+
+    #[test]
+    fn bang_is_major() {
+        assert_eq!(
+            conventional_rule_for_commit("feat!: drop support for Node 12"),
+            Some(ConventionalRule::Major)
+        );
+    }
+
+    #[test]
+    fn scoped_bang_is_major() {
+        assert_eq!(
+            conventional_rule_for_commit("fix(api)!: remove deprecated field"),
+            Some(ConventionalRule::Major)
+        );
+    }
+
+    #[test]
+    fn breaking_change_footer_is_major() {
+        let message = "feat: add new step\n\nBREAKING CHANGE: removes the old step";
+        assert_eq!(
+            conventional_rule_for_commit(message),
+            Some(ConventionalRule::Major)
+        );
+    }
+
+    #[test]
+    fn unrecognized_type_does_not_qualify() {
+        assert_eq!(conventional_rule_for_commit("wip: work in progress"), None);
+    }
+
+    #[test]
+    fn no_colon_does_not_qualify() {
+        assert_eq!(
+            conventional_rule_for_commit("a random commit message"),
+            None
+        );
+    }
+}
+
+/// Test-only helpers shared by the suites below that exercise git history (commit log parsing,
+/// tag lookup, changelog generation) against a throwaway repository.
+#[cfg(test)]
+mod test_git_fixtures {
+    use git2::{Repository, Signature};
+
+    pub(super) fn commit(repo: &Repository, message: &str) {
+        let signature = Signature::now("Test", "test@example.com").unwrap();
+        let mut index = repo.index().unwrap();
+        let tree_id = index.write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        let parents = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents = parents.iter().collect::<Vec<_>>();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod test_conventional_rule_from_commits {
+    use git2::Repository;
+    use tempfile::TempDir;
+
+    use super::test_git_fixtures::commit;
+    use super::*;
+
+    #[test]
+    fn defaults_to_patch_with_no_qualifying_commits() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        commit(&repo, "initial commit");
+
+        let rule = conventional_rule_from_commits_in(dir.path().to_str().unwrap()).unwrap();
+        assert_eq!(rule, ConventionalRule::Patch);
+    }
+
+    #[test]
+    fn picks_highest_precedence_rule_since_last_tag() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        commit(&repo, "chore: initial commit");
+        repo.tag_lightweight(
+            "v1.0.0",
+            &repo.head().unwrap().peel_to_commit().unwrap().into_object(),
+            false,
+        )
+        .unwrap();
+        commit(&repo, "fix: a bug");
+        commit(&repo, "feat: a feature");
+
+        let rule = conventional_rule_from_commits_in(dir.path().to_str().unwrap()).unwrap();
+        assert_eq!(rule, ConventionalRule::Minor);
+    }
+
+    #[test]
+    fn stops_at_the_last_version_tag() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        commit(&repo, "feat: a feature before the tag");
+        repo.tag_lightweight(
+            "v1.0.0",
+            &repo.head().unwrap().peel_to_commit().unwrap().into_object(),
+            false,
+        )
+        .unwrap();
+        commit(&repo, "fix: a bug after the tag");
+
+        let rule = conventional_rule_from_commits_in(dir.path().to_str().unwrap()).unwrap();
+        assert_eq!(rule, ConventionalRule::Patch);
+    }
+}
+
+#[cfg(test)]
+mod test_previous_version_and_changelog {
+    use git2::Repository;
+    use tempfile::TempDir;
+
+    use super::test_git_fixtures::commit;
+    use super::*;
+
+    #[test]
+    fn previous_version_reads_the_last_tag() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        commit(&repo, "chore: initial commit");
+        repo.tag_lightweight(
+            "v1.2.3",
+            &repo.head().unwrap().peel_to_commit().unwrap().into_object(),
+            false,
+        )
+        .unwrap();
+
+        let version = previous_version_in(dir.path().to_str().unwrap()).unwrap();
+        assert_eq!(version, Version::new(1, 2, 3));
+    }
+
+    #[test]
+    fn previous_version_errors_with_no_tags() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        commit(&repo, "chore: initial commit");
+
+        assert!(previous_version_in(dir.path().to_str().unwrap()).is_err());
+    }
+
+    #[test]
+    fn changelog_groups_commits_by_type() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        commit(&repo, "chore: initial commit");
+        repo.tag_lightweight(
+            "v1.0.0",
+            &repo.head().unwrap().peel_to_commit().unwrap().into_object(),
+            false,
+        )
+        .unwrap();
+        commit(&repo, "feat: add new step");
+        commit(&repo, "fix: a bug");
+
+        let changelog = changelog_entry_from_commits_in(dir.path().to_str().unwrap()).unwrap();
+        assert!(changelog.contains("### Features"));
+        assert!(changelog.contains("- feat: add new step"));
+        assert!(changelog.contains("### Fixes"));
+        assert!(changelog.contains("- fix: a bug"));
+    }
+
+    #[test]
+    fn changelog_excludes_non_fix_patch_commits() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        commit(&repo, "chore: initial commit");
+        repo.tag_lightweight(
+            "v1.0.0",
+            &repo.head().unwrap().peel_to_commit().unwrap().into_object(),
+            false,
+        )
+        .unwrap();
+        commit(&repo, "fix: a bug");
+        commit(&repo, "docs: update readme");
+        commit(&repo, "chore: bump deps");
+
+        let changelog = changelog_entry_from_commits_in(dir.path().to_str().unwrap()).unwrap();
+        assert!(changelog.contains("### Fixes"));
+        assert!(changelog.contains("- fix: a bug"));
+        assert!(!changelog.contains("update readme"));
+        assert!(!changelog.contains("bump deps"));
+    }
+
+    #[test]
+    fn changelog_errors_with_no_qualifying_commits() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        commit(&repo, "chore: initial commit");
+
+        assert!(changelog_entry_from_commits_in(dir.path().to_str().unwrap()).is_err());
+    }
+}